@@ -0,0 +1,137 @@
+//! A negamax search with alpha-beta pruning over a material-plus-mobility
+//! evaluation, giving the web server an opponent to play against.
+
+use crate::rules::{do_move, is_in_check, legal_moves, undo_move};
+use crate::{Cell, Color, GameState, PieceType};
+
+const PAWN_VALUE: i32 = 100;
+const KNIGHT_VALUE: i32 = 300;
+const BISHOP_VALUE: i32 = 300;
+const ROOK_VALUE: i32 = 500;
+const QUEEN_VALUE: i32 = 900;
+
+/// Large enough that no real material/mobility score can approach it, so a
+/// mate always outranks every other outcome.
+const CHECKMATE_SCORE: i32 = 1_000_000;
+
+fn piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => PAWN_VALUE,
+        PieceType::Knight => KNIGHT_VALUE,
+        PieceType::Bishop => BISHOP_VALUE,
+        PieceType::Rook => ROOK_VALUE,
+        PieceType::Queen => QUEEN_VALUE,
+        PieceType::King => 0,
+    }
+}
+
+/// Material-plus-mobility evaluation from `side`'s perspective, in centipawns.
+pub fn evaluate(state: &GameState, side: Color) -> i32 {
+    let mut material = 0;
+    for row in state.board.iter() {
+        for cell in row.iter() {
+            if let Cell::Piece(color, piece_type) = cell {
+                let value = piece_value(*piece_type);
+                material += if *color == side { value } else { -value };
+            }
+        }
+    }
+
+    let mobility = legal_moves(state, side).len() as i32 - legal_moves(state, side.opposite()).len() as i32;
+    material + mobility
+}
+
+fn negamax(state: &mut GameState, side: Color, depth: u32, mut alpha: i32, beta: i32) -> i32 {
+    let moves = legal_moves(state, side);
+
+    if moves.is_empty() {
+        // Deeper leftover depth means the mate was reached in fewer plies,
+        // so it scores more extreme and negamax's -max(-score) will prefer it.
+        return if is_in_check(&state.board, side) {
+            -(CHECKMATE_SCORE + depth as i32)
+        } else {
+            0 // stalemate
+        };
+    }
+
+    if depth == 0 {
+        return evaluate(state, side);
+    }
+
+    let mut best = i32::MIN + 1;
+    for (from, to) in moves {
+        let undo = do_move(state, &from, &to);
+        let score = -negamax(state, side.opposite(), depth - 1, -beta, -alpha);
+        undo_move(state, &from, &to, undo);
+        if score > best {
+            best = score;
+        }
+        if best > alpha {
+            alpha = best;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+/// Searches `depth` plies deep for the best move for `side`, or `None` if
+/// there are no legal moves (checkmate or stalemate). Moves are pushed and
+/// popped on `state` in place rather than cloned.
+pub fn search(state: &mut GameState, side: Color, depth: u32) -> Option<(String, String)> {
+    let moves = legal_moves(state, side);
+    let (mut alpha, beta) = (i32::MIN + 1, i32::MAX - 1);
+    let mut best_move = None;
+    let mut best_score = i32::MIN + 1;
+
+    for (from, to) in moves {
+        let undo = do_move(state, &from, &to);
+        let score = -negamax(state, side.opposite(), depth.saturating_sub(1), -beta, -alpha);
+        undo_move(state, &from, &to, undo);
+        if score > best_score {
+            best_score = score;
+            best_move = Some((from, to));
+        }
+        if best_score > alpha {
+            alpha = best_score;
+        }
+    }
+
+    best_move
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_starting_position_is_balanced() {
+        let state = GameState::default();
+        assert_eq!(evaluate(&state, Color::White), 0);
+        assert_eq!(evaluate(&state, Color::Black), 0);
+    }
+
+    #[test]
+    fn test_search_finds_mate_in_one() {
+        // Back-rank mate: Re1-e8# with the black king boxed in by its own pawns.
+        let mut state = crate::parse_fen("6k1/5ppp/8/8/8/8/8/4R2K w - - 0 1").unwrap();
+        let (from, to) = search(&mut state, Color::White, 2).unwrap();
+        assert_eq!((from.as_str(), to.as_str()), ("e1", "e8"));
+    }
+
+    #[test]
+    fn test_search_returns_none_when_no_legal_moves() {
+        // Black to move, stalemated.
+        let mut state = crate::parse_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(search(&mut state, Color::Black, 2), None);
+    }
+
+    #[test]
+    fn test_search_leaves_state_unchanged_after_returning() {
+        let mut state = GameState::default();
+        let before = state.clone();
+        search(&mut state, Color::White, 2);
+        assert_eq!(state, before);
+    }
+}
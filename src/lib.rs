@@ -1,53 +1,329 @@
-pub fn initialize_board() -> [[&'static str; 8]; 8] {
+pub mod engine;
+pub mod perft;
+pub mod rules;
+pub mod zobrist;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub fn opposite(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceType {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl PieceType {
+    /// The uppercase FEN letter for this piece type, e.g. knight is `N`.
+    pub fn letter(self) -> char {
+        match self {
+            PieceType::Pawn => 'P',
+            PieceType::Knight => 'N',
+            PieceType::Bishop => 'B',
+            PieceType::Rook => 'R',
+            PieceType::Queen => 'Q',
+            PieceType::King => 'K',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Piece(Color, PieceType),
+}
+
+pub type Board = [[Cell; 8]; 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+
+impl Default for CastlingRights {
+    fn default() -> Self {
+        CastlingRights {
+            white_kingside: true,
+            white_queenside: true,
+            black_kingside: true,
+            black_queenside: true,
+        }
+    }
+}
+
+/// Everything a FEN string encodes: the board plus the side to move,
+/// castling rights, en-passant target, and the two move counters. Also
+/// carries the Zobrist hash of the position, kept up to date incrementally
+/// by `rules::do_move`/`rules::undo_move`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameState {
+    pub board: Board,
+    pub side_to_move: Color,
+    pub castling: CastlingRights,
+    pub en_passant: Option<(usize, usize)>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    pub hash: u64,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        let mut state = GameState {
+            board: initialize_board(),
+            side_to_move: Color::White,
+            castling: CastlingRights::default(),
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            hash: 0,
+        };
+        state.hash = zobrist::zobrist_hash(&state);
+        state
+    }
+}
+
+pub fn initialize_board() -> Board {
+    use Color::*;
+    use PieceType::*;
+
+    let back_rank = |color: Color| -> [Cell; 8] {
+        [
+            Cell::Piece(color, Rook),
+            Cell::Piece(color, Knight),
+            Cell::Piece(color, Bishop),
+            Cell::Piece(color, Queen),
+            Cell::Piece(color, King),
+            Cell::Piece(color, Bishop),
+            Cell::Piece(color, Knight),
+            Cell::Piece(color, Rook),
+        ]
+    };
+
     [
-        ["R", "Kn", "B", "Q", "K", "B", "Kn", "R"], // Black pieces
-        ["P"; 8],                                   // Black pawns
-        [" "; 8],                                   // Empty row
-        [" "; 8],                                   // Empty row
-        [" "; 8],                                   // Empty row
-        [" "; 8],                                   // Empty row
-        ["P"; 8],                                   // White pawns
-        ["R", "Kn", "B", "Q", "K", "B", "Kn", "R"], // White pieces
+        back_rank(Black),
+        [Cell::Piece(Black, Pawn); 8],
+        [Cell::Empty; 8],
+        [Cell::Empty; 8],
+        [Cell::Empty; 8],
+        [Cell::Empty; 8],
+        [Cell::Piece(White, Pawn); 8],
+        back_rank(White),
     ]
 }
 
+/// Parses an algebraic square like `"e2"` into `(row, col)`, checking that
+/// the file is `a`-`h` and the rank is `1`-`8` before doing arithmetic on
+/// them, so malformed input is rejected instead of under/overflowing.
+pub fn try_parse_position(pos: &str) -> Result<(usize, usize), &'static str> {
+    let mut chars = pos.chars();
+    let file = chars.next().ok_or("square is missing a file")?;
+    let rank = chars.next().ok_or("square is missing a rank")?;
+
+    if !('a'..='h').contains(&file) {
+        return Err("square file must be between 'a' and 'h'");
+    }
+    if !('1'..='8').contains(&rank) {
+        return Err("square rank must be between '1' and '8'");
+    }
+
+    let col = file as usize - 'a' as usize;
+    let row = 8 - rank.to_digit(10).unwrap() as usize;
+    Ok((row, col))
+}
+
+/// Parses an algebraic square like `"e2"` into `(row, col)`. Panics if `pos`
+/// isn't a valid square; callers working from untrusted input (e.g. a move
+/// string from a client) should use `try_parse_position` instead.
 pub fn parse_position(pos: &str) -> (usize, usize) {
-    let col = pos.chars().next().unwrap() as usize - 'a' as usize;
-    let row = 8 - pos.chars().nth(1).unwrap().to_digit(10).unwrap() as usize;
-    (row, col)
+    try_parse_position(pos).expect("parse_position called with an invalid square")
 }
 
-pub fn make_move(
-    board: &mut [[&str; 8]; 8],
-    from: &str,
-    to: &str,
-) -> Result<(), &'static str> {
-    let (from_row, from_col) = parse_position(from);
-    let (to_row, to_col) = parse_position(to);
+/// Parses a full FEN record (all six space-separated fields) into a `GameState`.
+pub fn parse_fen(fen: &str) -> Result<GameState, &'static str> {
+    let mut fields = fen.split_whitespace();
+    let placement = fields.next().ok_or("FEN is missing the piece placement field")?;
+    let side = fields.next().unwrap_or("w");
+    let castling = fields.next().unwrap_or("-");
+    let en_passant = fields.next().unwrap_or("-");
+    let halfmove = fields.next().unwrap_or("0");
+    let fullmove = fields.next().unwrap_or("1");
 
-    if from_row >= 8 || from_col >= 8 || to_row >= 8 || to_col >= 8 {
-        return Err("Move out of bounds");
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err("FEN piece placement must have 8 ranks");
     }
 
-    if board[from_row][from_col] == " " {
-        return Err("No piece at the source position");
+    let mut board = [[Cell::Empty; 8]; 8];
+    for (row, rank) in ranks.iter().enumerate() {
+        let mut col = 0usize;
+        for ch in rank.chars() {
+            if let Some(empty_count) = ch.to_digit(10) {
+                col += empty_count as usize;
+            } else {
+                let color = if ch.is_uppercase() { Color::White } else { Color::Black };
+                let piece_type = match ch.to_ascii_uppercase() {
+                    'P' => PieceType::Pawn,
+                    'N' => PieceType::Knight,
+                    'B' => PieceType::Bishop,
+                    'R' => PieceType::Rook,
+                    'Q' => PieceType::Queen,
+                    'K' => PieceType::King,
+                    _ => return Err("FEN contains an unrecognized piece letter"),
+                };
+                if col >= 8 {
+                    return Err("FEN rank lists more than 8 squares");
+                }
+                board[row][col] = Cell::Piece(color, piece_type);
+                col += 1;
+            }
+        }
+        if col != 8 {
+            return Err("FEN rank does not add up to 8 squares");
+        }
     }
 
-    board[to_row][to_col] = board[from_row][from_col];
-    board[from_row][from_col] = " ";
-    Ok(())
+    let side_to_move = match side {
+        "w" => Color::White,
+        "b" => Color::Black,
+        _ => return Err("FEN side-to-move field must be 'w' or 'b'"),
+    };
+
+    let castling_rights = CastlingRights {
+        white_kingside: castling.contains('K'),
+        white_queenside: castling.contains('Q'),
+        black_kingside: castling.contains('k'),
+        black_queenside: castling.contains('q'),
+    };
+
+    let en_passant_target = if en_passant == "-" {
+        None
+    } else {
+        let mut chars = en_passant.chars();
+        let file = chars.next().ok_or("FEN en-passant square is malformed")?;
+        let rank = chars.next().ok_or("FEN en-passant square is malformed")?;
+        if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+            return Err("FEN en-passant square is out of range");
+        }
+        let col = file as usize - 'a' as usize;
+        let row = 8 - rank.to_digit(10).unwrap() as usize;
+        Some((row, col))
+    };
+
+    let halfmove_clock = halfmove.parse().map_err(|_| "FEN halfmove clock is not a number")?;
+    let fullmove_number = fullmove.parse().map_err(|_| "FEN fullmove number is not a number")?;
+
+    let mut state = GameState {
+        board,
+        side_to_move,
+        castling: castling_rights,
+        en_passant: en_passant_target,
+        halfmove_clock,
+        fullmove_number,
+        hash: 0,
+    };
+    state.hash = zobrist::zobrist_hash(&state);
+    Ok(state)
 }
 
-pub fn render_chessboard(board: &[[&str; 8]; 8]) -> String {
+/// Serializes a `GameState` back into a full FEN record.
+pub fn to_fen(state: &GameState) -> String {
+    let mut placement = String::new();
+    for (row, rank) in state.board.iter().enumerate() {
+        let mut empty_run = 0u32;
+        for cell in rank.iter() {
+            match cell {
+                Cell::Empty => empty_run += 1,
+                Cell::Piece(color, piece_type) => {
+                    if empty_run > 0 {
+                        placement.push_str(&empty_run.to_string());
+                        empty_run = 0;
+                    }
+                    let letter = piece_type.letter();
+                    placement.push(if *color == Color::White {
+                        letter
+                    } else {
+                        letter.to_ascii_lowercase()
+                    });
+                }
+            }
+        }
+        if empty_run > 0 {
+            placement.push_str(&empty_run.to_string());
+        }
+        if row != 7 {
+            placement.push('/');
+        }
+    }
+
+    let side = if state.side_to_move == Color::White { "w" } else { "b" };
+
+    let mut castling = String::new();
+    if state.castling.white_kingside {
+        castling.push('K');
+    }
+    if state.castling.white_queenside {
+        castling.push('Q');
+    }
+    if state.castling.black_kingside {
+        castling.push('k');
+    }
+    if state.castling.black_queenside {
+        castling.push('q');
+    }
+    if castling.is_empty() {
+        castling.push('-');
+    }
+
+    let en_passant = match state.en_passant {
+        Some((row, col)) => format!("{}{}", (b'a' + col as u8) as char, 8 - row),
+        None => "-".to_string(),
+    };
+
+    format!(
+        "{} {} {} {} {} {}",
+        placement, side, castling, en_passant, state.halfmove_clock, state.fullmove_number
+    )
+}
+
+pub fn render_chessboard(board: &Board) -> String {
     let mut html = String::from("<table style='border-collapse: collapse;'>");
     for (i, row) in board.iter().enumerate() {
         html.push_str("<tr>");
         for (j, cell) in row.iter().enumerate() {
             let background_color = if (i + j) % 2 == 0 { "#eee" } else { "#333" };
             let text_color = if (i + j) % 2 == 0 { "#000" } else { "#fff" };
+            let symbol = match cell {
+                Cell::Empty => String::new(),
+                Cell::Piece(color, piece_type) => {
+                    let letter = piece_type.letter();
+                    if *color == Color::White {
+                        letter.to_string()
+                    } else {
+                        letter.to_ascii_lowercase().to_string()
+                    }
+                }
+            };
             html.push_str(&format!(
                 "<td style='width: 50px; height: 50px; text-align: center; background-color: {}; color: {}; border: 1px solid #000;'>{}</td>",
-                background_color, text_color, cell
+                background_color, text_color, symbol
             ));
         }
         html.push_str("</tr>");
@@ -63,9 +339,9 @@ mod tests {
     #[test]
     fn test_initialize_board() {
         let board = initialize_board();
-        assert_eq!(board[0][0], "R"); // Rook at a8
-        assert_eq!(board[1][0], "P"); // Pawn at a7
-        assert_eq!(board[7][4], "K"); // King at e1
+        assert_eq!(board[0][0], Cell::Piece(Color::Black, PieceType::Rook)); // Rook at a8
+        assert_eq!(board[1][0], Cell::Piece(Color::Black, PieceType::Pawn)); // Pawn at a7
+        assert_eq!(board[7][4], Cell::Piece(Color::White, PieceType::King)); // King at e1
     }
 
     #[test]
@@ -75,10 +351,33 @@ mod tests {
     }
 
     #[test]
-    fn test_make_move() {
-        let mut board = initialize_board();
-        assert!(make_move(&mut board, "e2", "e4").is_ok());
-        assert_eq!(board[4][4], "P"); // Pawn at e4
-        assert_eq!(board[6][4], " "); // e2 is empty
+    fn test_try_parse_position_rejects_malformed_square() {
+        assert!(try_parse_position("e9").is_err()); // rank out of range
+        assert!(try_parse_position("ex").is_err()); // rank not a digit
+        assert!(try_parse_position("E2").is_err()); // file out of range
+        assert!(try_parse_position("e").is_err()); // missing rank
+    }
+
+    #[test]
+    fn test_parse_fen_starting_position() {
+        let state = parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(state.board, initialize_board());
+        assert_eq!(state.side_to_move, Color::White);
+        assert_eq!(state.castling, CastlingRights::default());
+        assert_eq!(state.en_passant, None);
+        assert_eq!(state.halfmove_clock, 0);
+        assert_eq!(state.fullmove_number, 1);
+    }
+
+    #[test]
+    fn test_fen_round_trip() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        let state = parse_fen(fen).unwrap();
+        assert_eq!(to_fen(&state), fen);
+    }
+
+    #[test]
+    fn test_parse_fen_rejects_malformed_input() {
+        assert!(parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1").is_err());
     }
 }
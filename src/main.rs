@@ -1,11 +1,28 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-use rusty_chess::{initialize_board, make_move, render_chessboard}; // Import from lib.rs
+use rusty_chess::engine::search;
+use rusty_chess::rules::apply_move;
+use rusty_chess::zobrist::is_draw;
+use rusty_chess::{parse_fen, render_chessboard, to_fen, GameState}; // Import from lib.rs
 use serde::Deserialize;
 use std::sync::Mutex;
 
+const DEFAULT_AI_DEPTH: u32 = 3;
+
 #[derive(Debug)]
 struct AppState {
-    board: Mutex<[[&'static str; 8]; 8]>,
+    game: Mutex<GameState>,
+    /// Zobrist hash of every position reached so far, for threefold-repetition checks.
+    history: Mutex<Vec<u64>>,
+}
+
+impl AppState {
+    fn new(game: GameState) -> Self {
+        let history = Mutex::new(vec![game.hash]);
+        AppState {
+            game: Mutex::new(game),
+            history,
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -13,9 +30,34 @@ struct MoveInput {
     move_notation: String,
 }
 
-async fn index(data: web::Data<AppState>) -> impl Responder {
-    let board = data.board.lock().unwrap();
-    let chessboard_html = render_chessboard(&*board);
+#[derive(Deserialize)]
+struct IndexQuery {
+    fen: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AiInput {
+    depth: Option<u32>,
+}
+
+async fn index(data: web::Data<AppState>, query: web::Query<IndexQuery>) -> impl Responder {
+    if let Some(fen) = &query.fen {
+        match parse_fen(fen) {
+            Ok(loaded) => {
+                *data.history.lock().unwrap() = vec![loaded.hash];
+                *data.game.lock().unwrap() = loaded;
+            }
+            Err(message) => return HttpResponse::BadRequest().body(message),
+        }
+    }
+
+    let game = data.game.lock().unwrap();
+    let chessboard_html = render_chessboard(&game.board);
+    let draw_notice = if is_draw(&game, &data.history.lock().unwrap()) {
+        "<p>Draw (threefold repetition or the fifty-move rule).</p>"
+    } else {
+        ""
+    };
     HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(format!(
@@ -30,9 +72,13 @@ async fn index(data: web::Data<AppState>) -> impl Responder {
                     <input type='text' name='move_notation' placeholder='Enter move (e.g., e2e4)' required>
                     <button type='submit'>Make Move</button>
                 </form>
+                <p>FEN: {}</p>
+                {}
                 {}
             </body>
             </html>",
+            to_fen(&game),
+            draw_notice,
             chessboard_html
         ))
 }
@@ -48,27 +94,44 @@ async fn make_move_endpoint(
     let from = &move_notation[0..2];
     let to = &move_notation[2..4];
 
-    let mut board = data.board.lock().unwrap();
-    if make_move(&mut board, from, to).is_err() {
-        return HttpResponse::BadRequest().body("Invalid move.");
+    let mut game = data.game.lock().unwrap();
+    if let Err(message) = apply_move(&mut game, from, to) {
+        return HttpResponse::BadRequest().body(message);
     }
+    data.history.lock().unwrap().push(game.hash);
 
     HttpResponse::SeeOther()
         .append_header(("Location", "/"))
         .finish()
 }
 
+async fn ai_move_endpoint(data: web::Data<AppState>, form: web::Form<AiInput>) -> impl Responder {
+    let depth = form.depth.unwrap_or(DEFAULT_AI_DEPTH);
+    let mut game = data.game.lock().unwrap();
+    let side = game.side_to_move;
+
+    match search(&mut game, side, depth) {
+        Some((from, to)) => {
+            if let Err(message) = apply_move(&mut game, &from, &to) {
+                return HttpResponse::InternalServerError().body(message);
+            }
+            data.history.lock().unwrap().push(game.hash);
+            HttpResponse::SeeOther().append_header(("Location", "/")).finish()
+        }
+        None => HttpResponse::Ok().body("No legal moves available (checkmate or stalemate)."),
+    }
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let state = web::Data::new(AppState {
-        board: Mutex::new(initialize_board()),
-    });
+    let state = web::Data::new(AppState::new(GameState::default()));
 
     HttpServer::new(move || {
         App::new()
             .app_data(state.clone())
             .route("/", web::get().to(index))
             .route("/move", web::post().to(make_move_endpoint))
+            .route("/ai", web::post().to(ai_move_endpoint))
     })
         .bind("127.0.0.1:8080")?
         .run()
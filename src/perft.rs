@@ -0,0 +1,54 @@
+//! Perft: a brute-force move-count oracle used to pin down correct legal
+//! move generation against well-known reference counts. Within the depths
+//! tested here (up to 4) the starting position exercises check evasion but
+//! reaches no castling, en-passant capture or promotion. See
+//! `test_perft_reaches_promotion_counts` for coverage of promotion.
+
+use crate::rules::{do_move, legal_moves, undo_move};
+use crate::{Color, GameState};
+
+/// Counts the leaf nodes reachable in exactly `depth` plies from `state`,
+/// pushing and popping moves on `state` in place via `do_move`/`undo_move`.
+pub fn perft(state: &mut GameState, side: Color, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let mut nodes = 0;
+    for (from, to) in legal_moves(state, side) {
+        let undo = do_move(state, &from, &to);
+        nodes += perft(state, side.opposite(), depth - 1);
+        undo_move(state, &from, &to, undo);
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perft_from_starting_position() {
+        let mut state = GameState::default();
+        assert_eq!(perft(&mut state, Color::White, 1), 20);
+        assert_eq!(perft(&mut state, Color::White, 2), 400);
+        assert_eq!(perft(&mut state, Color::White, 3), 8902);
+        assert_eq!(perft(&mut state, Color::White, 4), 197281);
+    }
+
+    /// The well-known "promotions" test position, whose pawns sit one step
+    /// from queening, reaches promotion within the depths tested here —
+    /// unlike the starting position above. This engine only promotes to a
+    /// queen (no underpromotion choice), so these counts are lower than the
+    /// standard perft oracle values for this FEN, which also count
+    /// knight/bishop/rook promotions; they instead pin down this engine's
+    /// own queen-only behavior, and would have caught the bug where `do_move`
+    /// left a promoting pawn as a pawn.
+    #[test]
+    fn test_perft_reaches_promotion_counts() {
+        let mut state = crate::parse_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N w - - 0 1").unwrap();
+        assert_eq!(perft(&mut state, Color::White, 1), 15);
+        assert_eq!(perft(&mut state, Color::White, 2), 210);
+        assert_eq!(perft(&mut state, Color::White, 3), 3253);
+    }
+}
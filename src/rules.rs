@@ -0,0 +1,630 @@
+//! Move legality: per-piece pseudo-legal generation, attack detection, and
+//! the self-check filter that turns pseudo-legal moves into legal ones.
+
+use crate::{Board, CastlingRights, Cell, Color, GameState, PieceType};
+
+const ROOK_DIRS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const BISHOP_DIRS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+const KNIGHT_OFFSETS: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+const KING_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+fn in_bounds(row: isize, col: isize) -> bool {
+    (0..8).contains(&row) && (0..8).contains(&col)
+}
+
+fn square_to_algebraic(square: (usize, usize)) -> String {
+    format!("{}{}", (b'a' + square.1 as u8) as char, 8 - square.0)
+}
+
+/// True if `square` is attacked by any piece of `by_color` on `board`.
+pub fn is_attacked(board: &Board, square: (usize, usize), by_color: Color) -> bool {
+    let (row, col) = (square.0 as isize, square.1 as isize);
+
+    for &(dr, dc) in &KNIGHT_OFFSETS {
+        let (r, c) = (row + dr, col + dc);
+        if in_bounds(r, c) && board[r as usize][c as usize] == Cell::Piece(by_color, PieceType::Knight) {
+            return true;
+        }
+    }
+
+    for &(dr, dc) in &KING_OFFSETS {
+        let (r, c) = (row + dr, col + dc);
+        if in_bounds(r, c) && board[r as usize][c as usize] == Cell::Piece(by_color, PieceType::King) {
+            return true;
+        }
+    }
+
+    for &(dr, dc) in &ROOK_DIRS {
+        let (mut r, mut c) = (row + dr, col + dc);
+        while in_bounds(r, c) {
+            match board[r as usize][c as usize] {
+                Cell::Empty => {}
+                Cell::Piece(color, piece_type) if color == by_color => {
+                    if piece_type == PieceType::Rook || piece_type == PieceType::Queen {
+                        return true;
+                    }
+                    break;
+                }
+                Cell::Piece(_, _) => break,
+            }
+            r += dr;
+            c += dc;
+        }
+    }
+
+    for &(dr, dc) in &BISHOP_DIRS {
+        let (mut r, mut c) = (row + dr, col + dc);
+        while in_bounds(r, c) {
+            match board[r as usize][c as usize] {
+                Cell::Empty => {}
+                Cell::Piece(color, piece_type) if color == by_color => {
+                    if piece_type == PieceType::Bishop || piece_type == PieceType::Queen {
+                        return true;
+                    }
+                    break;
+                }
+                Cell::Piece(_, _) => break,
+            }
+            r += dr;
+            c += dc;
+        }
+    }
+
+    // A pawn of `by_color` attacks diagonally forward, so the attacker sits
+    // one rank behind `square` relative to its own direction of travel.
+    let pawn_rank_offset = if by_color == Color::White { 1 } else { -1 };
+    for dc in [-1, 1] {
+        let (r, c) = (row + pawn_rank_offset, col + dc);
+        if in_bounds(r, c) && board[r as usize][c as usize] == Cell::Piece(by_color, PieceType::Pawn) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn find_king(board: &Board, color: Color) -> Option<(usize, usize)> {
+    for (row, rank) in board.iter().enumerate() {
+        for (col, cell) in rank.iter().enumerate() {
+            if *cell == Cell::Piece(color, PieceType::King) {
+                return Some((row, col));
+            }
+        }
+    }
+    None
+}
+
+/// True if `color`'s king is currently attacked on `board`.
+pub fn is_in_check(board: &Board, color: Color) -> bool {
+    match find_king(board, color) {
+        Some(square) => is_attacked(board, square, color.opposite()),
+        None => false,
+    }
+}
+
+fn sliding_destinations(board: &Board, from: (usize, usize), color: Color, dirs: &[(isize, isize)]) -> Vec<(usize, usize)> {
+    let mut destinations = Vec::new();
+    for &(dr, dc) in dirs {
+        let (mut r, mut c) = (from.0 as isize + dr, from.1 as isize + dc);
+        while in_bounds(r, c) {
+            match board[r as usize][c as usize] {
+                Cell::Empty => destinations.push((r as usize, c as usize)),
+                Cell::Piece(piece_color, _) => {
+                    if piece_color != color {
+                        destinations.push((r as usize, c as usize));
+                    }
+                    break;
+                }
+            }
+            r += dr;
+            c += dc;
+        }
+    }
+    destinations
+}
+
+/// Pseudo-legal destinations for the piece at `from`, ignoring whether the
+/// move would leave the mover's own king in check.
+fn pseudo_legal_destinations(state: &GameState, from: (usize, usize)) -> Vec<(usize, usize)> {
+    let board = &state.board;
+    let (piece_color, piece_type) = match board[from.0][from.1] {
+        Cell::Piece(color, piece_type) => (color, piece_type),
+        Cell::Empty => return Vec::new(),
+    };
+
+    match piece_type {
+        PieceType::Knight => KNIGHT_OFFSETS
+            .iter()
+            .filter_map(|&(dr, dc)| {
+                let (r, c) = (from.0 as isize + dr, from.1 as isize + dc);
+                if !in_bounds(r, c) {
+                    return None;
+                }
+                match board[r as usize][c as usize] {
+                    Cell::Empty => Some((r as usize, c as usize)),
+                    Cell::Piece(color, _) if color != piece_color => Some((r as usize, c as usize)),
+                    Cell::Piece(_, _) => None,
+                }
+            })
+            .collect(),
+        PieceType::Bishop => sliding_destinations(board, from, piece_color, &BISHOP_DIRS),
+        PieceType::Rook => sliding_destinations(board, from, piece_color, &ROOK_DIRS),
+        PieceType::Queen => {
+            let mut destinations = sliding_destinations(board, from, piece_color, &ROOK_DIRS);
+            destinations.extend(sliding_destinations(board, from, piece_color, &BISHOP_DIRS));
+            destinations
+        }
+        PieceType::King => {
+            let mut destinations: Vec<(usize, usize)> = KING_OFFSETS
+                .iter()
+                .filter_map(|&(dr, dc)| {
+                    let (r, c) = (from.0 as isize + dr, from.1 as isize + dc);
+                    if !in_bounds(r, c) {
+                        return None;
+                    }
+                    match board[r as usize][c as usize] {
+                        Cell::Empty => Some((r as usize, c as usize)),
+                        Cell::Piece(color, _) if color != piece_color => Some((r as usize, c as usize)),
+                        Cell::Piece(_, _) => None,
+                    }
+                })
+                .collect();
+
+            let home_row = if piece_color == Color::White { 7 } else { 0 };
+            if from == (home_row, 4) && !is_in_check(board, piece_color) {
+                let opponent = piece_color.opposite();
+                let (kingside, queenside) = if piece_color == Color::White {
+                    (state.castling.white_kingside, state.castling.white_queenside)
+                } else {
+                    (state.castling.black_kingside, state.castling.black_queenside)
+                };
+
+                if kingside
+                    && board[home_row][5] == Cell::Empty
+                    && board[home_row][6] == Cell::Empty
+                    && !is_attacked(board, (home_row, 5), opponent)
+                    && !is_attacked(board, (home_row, 6), opponent)
+                {
+                    destinations.push((home_row, 6));
+                }
+
+                if queenside
+                    && board[home_row][1] == Cell::Empty
+                    && board[home_row][2] == Cell::Empty
+                    && board[home_row][3] == Cell::Empty
+                    && !is_attacked(board, (home_row, 3), opponent)
+                    && !is_attacked(board, (home_row, 2), opponent)
+                {
+                    destinations.push((home_row, 2));
+                }
+            }
+
+            destinations
+        }
+        PieceType::Pawn => {
+            let mut destinations = Vec::new();
+            let direction: isize = if piece_color == Color::White { -1 } else { 1 };
+            let start_row = if piece_color == Color::White { 6 } else { 1 };
+
+            let one_step = from.0 as isize + direction;
+            if in_bounds(one_step, from.1 as isize) && board[one_step as usize][from.1] == Cell::Empty {
+                destinations.push((one_step as usize, from.1));
+
+                let two_step = from.0 as isize + 2 * direction;
+                if from.0 == start_row && board[two_step as usize][from.1] == Cell::Empty {
+                    destinations.push((two_step as usize, from.1));
+                }
+            }
+
+            for dc in [-1, 1] {
+                let (r, c) = (from.0 as isize + direction, from.1 as isize + dc);
+                if !in_bounds(r, c) {
+                    continue;
+                }
+                let (r, c) = (r as usize, c as usize);
+                match board[r][c] {
+                    Cell::Piece(color, _) if color != piece_color => destinations.push((r, c)),
+                    Cell::Empty if state.en_passant == Some((r, c)) => destinations.push((r, c)),
+                    _ => {}
+                }
+            }
+
+            destinations
+        }
+    }
+}
+
+/// Applies `from -> to` to a scratch copy of the board, handling en-passant
+/// captures and castling rook moves, for the sole purpose of checking
+/// whether the resulting position leaves the mover's king in check.
+fn simulate_move(state: &GameState, from: (usize, usize), to: (usize, usize)) -> Board {
+    let mut board = state.board;
+    let piece_type = match board[from.0][from.1] {
+        Cell::Piece(_, piece_type) => piece_type,
+        Cell::Empty => return board,
+    };
+
+    if piece_type == PieceType::Pawn && Some(to) == state.en_passant && to.1 != from.1 {
+        board[from.0][to.1] = Cell::Empty;
+    }
+
+    if piece_type == PieceType::King && from.1 == 4 && (to.1 as isize - from.1 as isize).abs() == 2 {
+        let home_row = from.0;
+        if to.1 == 6 {
+            board[home_row][5] = board[home_row][7];
+            board[home_row][7] = Cell::Empty;
+        } else if to.1 == 2 {
+            board[home_row][3] = board[home_row][0];
+            board[home_row][0] = Cell::Empty;
+        }
+    }
+
+    board[to.0][to.1] = board[from.0][from.1];
+    board[from.0][from.1] = Cell::Empty;
+    board
+}
+
+/// All legal moves for `color` in `state`, as `(from, to)` algebraic pairs.
+pub fn legal_moves(state: &GameState, color: Color) -> Vec<(String, String)> {
+    let mut moves = Vec::new();
+    for (row, rank) in state.board.iter().enumerate() {
+        for (col, cell) in rank.iter().enumerate() {
+            if !matches!(cell, Cell::Piece(piece_color, _) if *piece_color == color) {
+                continue;
+            }
+            let from = (row, col);
+            for to in pseudo_legal_destinations(state, from) {
+                let resulting_board = simulate_move(state, from, to);
+                if !is_in_check(&resulting_board, color) {
+                    moves.push((square_to_algebraic(from), square_to_algebraic(to)));
+                }
+            }
+        }
+    }
+    moves
+}
+
+/// Validates that `from -> to` is a legal move for the side to move.
+pub fn validate_move(state: &GameState, from: &str, to: &str) -> Result<(), &'static str> {
+    let from_square = crate::try_parse_position(from)?;
+    let to_square = crate::try_parse_position(to)?;
+
+    match state.board[from_square.0][from_square.1] {
+        Cell::Empty => return Err("No piece at the source position"),
+        Cell::Piece(color, _) if color != state.side_to_move => {
+            return Err("That piece does not belong to the side to move")
+        }
+        _ => {}
+    }
+
+    let from_algebraic = square_to_algebraic(from_square);
+    let to_algebraic = square_to_algebraic(to_square);
+    let is_legal = legal_moves(state, state.side_to_move)
+        .into_iter()
+        .any(|(candidate_from, candidate_to)| candidate_from == from_algebraic && candidate_to == to_algebraic);
+
+    if is_legal {
+        Ok(())
+    } else {
+        Err("Illegal move")
+    }
+}
+
+/// Everything `do_move` overwrites that can't be recovered from the
+/// resulting position, so `undo_move` can exactly reverse it.
+#[derive(Debug, Clone)]
+pub struct UndoState {
+    captured: Option<((usize, usize), Color, PieceType)>,
+    castling: CastlingRights,
+    en_passant: Option<(usize, usize)>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    /// True if the moved pawn reached the last rank and was promoted, so
+    /// `undo_move` knows to restore a pawn at the source square instead of
+    /// the promoted piece.
+    promoted: bool,
+}
+
+fn clear_castling_rights_for_rook_loss(castling: &mut CastlingRights, color: Color, square: (usize, usize)) {
+    match (color, square) {
+        (Color::White, (7, 0)) => castling.white_queenside = false,
+        (Color::White, (7, 7)) => castling.white_kingside = false,
+        (Color::Black, (0, 0)) => castling.black_queenside = false,
+        (Color::Black, (0, 7)) => castling.black_kingside = false,
+        _ => {}
+    }
+}
+
+/// Performs `from -> to` on `state` in place (no legality check — callers
+/// are expected to have generated the move via `legal_moves`) and returns
+/// everything needed to reverse it with `undo_move`. This lets a search
+/// push and pop moves on a single mutable board instead of cloning.
+pub fn do_move(state: &mut GameState, from: &str, to: &str) -> UndoState {
+    let from_square = crate::parse_position(from);
+    let to_square = crate::parse_position(to);
+    let (piece_color, piece_type) = match state.board[from_square.0][from_square.1] {
+        Cell::Piece(color, piece_type) => (color, piece_type),
+        Cell::Empty => panic!("do_move called with no piece at the source square"),
+    };
+
+    let undo = UndoState {
+        captured: None,
+        castling: state.castling,
+        en_passant: state.en_passant,
+        halfmove_clock: state.halfmove_clock,
+        fullmove_number: state.fullmove_number,
+        promoted: false,
+    };
+
+    let is_en_passant_capture =
+        piece_type == PieceType::Pawn && Some(to_square) == state.en_passant && to_square.1 != from_square.1;
+    let captured_square = if is_en_passant_capture {
+        (from_square.0, to_square.1)
+    } else {
+        to_square
+    };
+    let captured = match state.board[captured_square.0][captured_square.1] {
+        Cell::Piece(color, captured_type) => Some((captured_square, color, captured_type)),
+        Cell::Empty => None,
+    };
+    if let Some((square, color, captured_type)) = captured {
+        state.hash ^= crate::zobrist::piece_key(color, captured_type, square);
+    }
+    if is_en_passant_capture {
+        state.board[captured_square.0][captured_square.1] = Cell::Empty;
+    }
+
+    let is_castle = piece_type == PieceType::King && (to_square.1 as isize - from_square.1 as isize).abs() == 2;
+    if is_castle {
+        let home_row = from_square.0;
+        if to_square.1 == 6 {
+            state.board[home_row][5] = state.board[home_row][7];
+            state.board[home_row][7] = Cell::Empty;
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 7));
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 5));
+        } else {
+            state.board[home_row][3] = state.board[home_row][0];
+            state.board[home_row][0] = Cell::Empty;
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 0));
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 3));
+        }
+    }
+
+    let promotion_row = if piece_color == Color::White { 0 } else { 7 };
+    let is_promotion = piece_type == PieceType::Pawn && to_square.0 == promotion_row;
+    let placed_type = if is_promotion { PieceType::Queen } else { piece_type };
+
+    state.board[to_square.0][to_square.1] = Cell::Piece(piece_color, placed_type);
+    state.board[from_square.0][from_square.1] = Cell::Empty;
+    state.hash ^= crate::zobrist::piece_key(piece_color, piece_type, from_square);
+    state.hash ^= crate::zobrist::piece_key(piece_color, placed_type, to_square);
+
+    match (piece_color, from_square) {
+        (Color::White, (7, 4)) => {
+            state.castling.white_kingside = false;
+            state.castling.white_queenside = false;
+        }
+        (Color::Black, (0, 4)) => {
+            state.castling.black_kingside = false;
+            state.castling.black_queenside = false;
+        }
+        _ => clear_castling_rights_for_rook_loss(&mut state.castling, piece_color, from_square),
+    }
+    if let Some((square, captured_color, _)) = captured {
+        clear_castling_rights_for_rook_loss(&mut state.castling, captured_color, square);
+    }
+    state.hash ^= crate::zobrist::castling_key_hash(undo.castling);
+    state.hash ^= crate::zobrist::castling_key_hash(state.castling);
+
+    if let Some((_, col)) = state.en_passant {
+        state.hash ^= crate::zobrist::en_passant_key(col);
+    }
+    state.en_passant = if piece_type == PieceType::Pawn && (to_square.0 as isize - from_square.0 as isize).abs() == 2 {
+        Some(((from_square.0 + to_square.0) / 2, from_square.1))
+    } else {
+        None
+    };
+    if let Some((_, col)) = state.en_passant {
+        state.hash ^= crate::zobrist::en_passant_key(col);
+    }
+
+    state.halfmove_clock = if captured.is_some() || piece_type == PieceType::Pawn {
+        0
+    } else {
+        state.halfmove_clock + 1
+    };
+
+    if piece_color == Color::Black {
+        state.fullmove_number += 1;
+    }
+
+    state.side_to_move = state.side_to_move.opposite();
+    state.hash ^= crate::zobrist::side_to_move_key();
+
+    UndoState { captured, promoted: is_promotion, ..undo }
+}
+
+/// Exactly reverses the `do_move(state, from, to)` that produced `undo`.
+pub fn undo_move(state: &mut GameState, from: &str, to: &str, undo: UndoState) {
+    let from_square = crate::parse_position(from);
+    let to_square = crate::parse_position(to);
+
+    let (piece_color, placed_type) = match state.board[to_square.0][to_square.1] {
+        Cell::Piece(color, piece_type) => (color, piece_type),
+        Cell::Empty => panic!("undo_move called with no piece at the destination square"),
+    };
+    let piece_type = if undo.promoted { PieceType::Pawn } else { placed_type };
+
+    let is_castle = piece_type == PieceType::King && (to_square.1 as isize - from_square.1 as isize).abs() == 2;
+    if is_castle {
+        let home_row = to_square.0;
+        if to_square.1 == 6 {
+            state.board[home_row][7] = state.board[home_row][5];
+            state.board[home_row][5] = Cell::Empty;
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 5));
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 7));
+        } else {
+            state.board[home_row][0] = state.board[home_row][3];
+            state.board[home_row][3] = Cell::Empty;
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 3));
+            state.hash ^= crate::zobrist::piece_key(piece_color, PieceType::Rook, (home_row, 0));
+        }
+    }
+
+    state.board[from_square.0][from_square.1] = Cell::Piece(piece_color, piece_type);
+    state.board[to_square.0][to_square.1] = Cell::Empty;
+    state.hash ^= crate::zobrist::piece_key(piece_color, placed_type, to_square);
+    state.hash ^= crate::zobrist::piece_key(piece_color, piece_type, from_square);
+
+    if let Some((square, color, captured_type)) = undo.captured {
+        state.board[square.0][square.1] = Cell::Piece(color, captured_type);
+        state.hash ^= crate::zobrist::piece_key(color, captured_type, square);
+    }
+
+    state.hash ^= crate::zobrist::castling_key_hash(state.castling);
+    state.hash ^= crate::zobrist::castling_key_hash(undo.castling);
+    state.castling = undo.castling;
+
+    let ep_hash = |ep: Option<(usize, usize)>| match ep {
+        Some((_, col)) => crate::zobrist::en_passant_key(col),
+        None => 0,
+    };
+    state.hash ^= ep_hash(state.en_passant);
+    state.hash ^= ep_hash(undo.en_passant);
+    state.en_passant = undo.en_passant;
+
+    state.halfmove_clock = undo.halfmove_clock;
+    state.fullmove_number = undo.fullmove_number;
+    state.side_to_move = state.side_to_move.opposite();
+    state.hash ^= crate::zobrist::side_to_move_key();
+}
+
+/// Validates that `from -> to` is legal, then applies it with `do_move`.
+pub fn apply_move(state: &mut GameState, from: &str, to: &str) -> Result<(), &'static str> {
+    validate_move(state, from, to)?;
+    do_move(state, from, to);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starting_position_has_twenty_legal_moves() {
+        let state = GameState::default();
+        assert_eq!(legal_moves(&state, Color::White).len(), 20);
+    }
+
+    #[test]
+    fn test_pawn_cannot_jump_over_blocker() {
+        let mut state = GameState::default();
+        state.board[5][4] = Cell::Piece(Color::Black, PieceType::Pawn);
+        let moves = legal_moves(&state, Color::White);
+        assert!(!moves.contains(&("e2".to_string(), "e4".to_string())));
+        assert!(!moves.contains(&("e2".to_string(), "e3".to_string())));
+    }
+
+    #[test]
+    fn test_king_cannot_move_into_check() {
+        let mut board = [[Cell::Empty; 8]; 8];
+        board[7][4] = Cell::Piece(Color::White, PieceType::King);
+        board[0][4] = Cell::Piece(Color::Black, PieceType::Rook);
+        let state = GameState {
+            board,
+            ..GameState::default()
+        };
+        let moves = legal_moves(&state, Color::White);
+        assert!(!moves.iter().any(|(_, to)| to == "e2"));
+    }
+
+    #[test]
+    fn test_validate_move_rejects_illegal_move() {
+        let state = GameState::default();
+        assert!(validate_move(&state, "e2", "e5").is_err());
+        assert!(validate_move(&state, "e2", "e4").is_ok());
+    }
+
+    #[test]
+    fn test_validate_move_rejects_out_of_bounds_square_instead_of_panicking() {
+        let state = GameState::default();
+        assert!(validate_move(&state, "i1", "i2").is_err());
+    }
+
+    #[test]
+    fn test_validate_move_rejects_malformed_square_instead_of_panicking() {
+        let state = GameState::default();
+        assert!(validate_move(&state, "e2", "e9").is_err()); // rank out of range
+        assert!(validate_move(&state, "e2", "ex").is_err()); // rank not a digit
+        assert!(validate_move(&state, "E2", "e4").is_err()); // file out of range
+    }
+
+    #[test]
+    fn test_apply_move_sets_en_passant_target_and_toggles_side() {
+        let mut state = GameState::default();
+        assert!(apply_move(&mut state, "e2", "e4").is_ok());
+        assert_eq!(state.en_passant, Some((5, 4)));
+        assert_eq!(state.side_to_move, Color::Black);
+        assert!(apply_move(&mut state, "e7", "e5").is_ok());
+        assert_eq!(state.en_passant, Some((2, 4)));
+    }
+
+    #[test]
+    fn test_do_move_undo_move_round_trip() {
+        let original = GameState::default();
+        let mut state = original.clone();
+
+        let undo = do_move(&mut state, "e2", "e4");
+        assert_ne!(state, original);
+        undo_move(&mut state, "e2", "e4", undo);
+        assert_eq!(state, original);
+    }
+
+    #[test]
+    fn test_undo_move_restores_en_passant_captured_pawn() {
+        let mut state = crate::parse_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let original = state.clone();
+
+        let undo = do_move(&mut state, "e5", "d6");
+        assert_eq!(state.board[3][3], Cell::Empty); // captured black pawn removed from d5
+
+        undo_move(&mut state, "e5", "d6", undo);
+        assert_eq!(state, original);
+    }
+
+    #[test]
+    fn test_do_move_promotes_pawn_on_last_rank() {
+        let mut state = crate::parse_fen("8/P6k/8/8/8/8/7p/7K w - - 0 1").unwrap();
+        do_move(&mut state, "a7", "a8");
+        assert_eq!(state.board[0][0], Cell::Piece(Color::White, PieceType::Queen));
+        assert_eq!(crate::to_fen(&state), "Q7/7k/8/8/8/8/7p/7K b - - 0 1");
+    }
+
+    #[test]
+    fn test_undo_move_restores_pawn_after_promotion() {
+        let original = crate::parse_fen("8/P6k/8/8/8/8/7p/7K w - - 0 1").unwrap();
+        let mut state = original.clone();
+
+        let undo = do_move(&mut state, "a7", "a8");
+        undo_move(&mut state, "a7", "a8", undo);
+        assert_eq!(state, original);
+    }
+}
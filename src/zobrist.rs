@@ -0,0 +1,188 @@
+//! Zobrist hashing: a reproducible table of random keys used to fingerprint
+//! positions for repetition detection (and, eventually, transposition
+//! tables in the search).
+
+use crate::{CastlingRights, Cell, Color, GameState, PieceType};
+use std::sync::OnceLock;
+
+/// Fixed so every run of the program builds the same table and therefore
+/// the same hashes for the same positions.
+const ZOBRIST_SEED: u64 = 0x9E3779B97F4A7C15;
+
+/// A small, fast, fixed-seed PRNG - good enough for generating a table of
+/// keys, not for anything security-sensitive.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+struct ZobristTable {
+    /// Indexed by `piece_index(color, piece_type)` then `row * 8 + col`.
+    piece_square: [[u64; 64]; 12],
+    side_to_move: u64,
+    /// [white_kingside, white_queenside, black_kingside, black_queenside]
+    castling: [u64; 4],
+    en_passant_file: [u64; 8],
+}
+
+impl ZobristTable {
+    fn new() -> Self {
+        let mut rng = SplitMix64::new(ZOBRIST_SEED);
+        let mut piece_square = [[0u64; 64]; 12];
+        for piece in piece_square.iter_mut() {
+            for square in piece.iter_mut() {
+                *square = rng.next();
+            }
+        }
+        let side_to_move = rng.next();
+        let mut castling = [0u64; 4];
+        for key in castling.iter_mut() {
+            *key = rng.next();
+        }
+        let mut en_passant_file = [0u64; 8];
+        for key in en_passant_file.iter_mut() {
+            *key = rng.next();
+        }
+        ZobristTable {
+            piece_square,
+            side_to_move,
+            castling,
+            en_passant_file,
+        }
+    }
+}
+
+fn table() -> &'static ZobristTable {
+    static TABLE: OnceLock<ZobristTable> = OnceLock::new();
+    TABLE.get_or_init(ZobristTable::new)
+}
+
+fn piece_index(color: Color, piece_type: PieceType) -> usize {
+    let type_index = match piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+    };
+    let color_index = if color == Color::White { 0 } else { 1 };
+    color_index * 6 + type_index
+}
+
+pub(crate) fn piece_key(color: Color, piece_type: PieceType, square: (usize, usize)) -> u64 {
+    table().piece_square[piece_index(color, piece_type)][square.0 * 8 + square.1]
+}
+
+pub(crate) fn side_to_move_key() -> u64 {
+    table().side_to_move
+}
+
+pub(crate) fn en_passant_key(col: usize) -> u64 {
+    table().en_passant_file[col]
+}
+
+pub(crate) fn castling_key_hash(castling: CastlingRights) -> u64 {
+    let keys = table().castling;
+    let mut hash = 0u64;
+    if castling.white_kingside {
+        hash ^= keys[0];
+    }
+    if castling.white_queenside {
+        hash ^= keys[1];
+    }
+    if castling.black_kingside {
+        hash ^= keys[2];
+    }
+    if castling.black_queenside {
+        hash ^= keys[3];
+    }
+    hash
+}
+
+/// Computes the Zobrist hash of `state` from scratch by XORing in the key
+/// for every occupied square plus the active state keys.
+pub fn zobrist_hash(state: &GameState) -> u64 {
+    let mut hash = 0u64;
+    for (row, rank) in state.board.iter().enumerate() {
+        for (col, cell) in rank.iter().enumerate() {
+            if let Cell::Piece(color, piece_type) = cell {
+                hash ^= piece_key(*color, *piece_type, (row, col));
+            }
+        }
+    }
+    if state.side_to_move == Color::Black {
+        hash ^= side_to_move_key();
+    }
+    hash ^= castling_key_hash(state.castling);
+    if let Some((_, col)) = state.en_passant {
+        hash ^= en_passant_key(col);
+    }
+    hash
+}
+
+/// True once `state` is drawn by the fifty-move rule or by the same
+/// position (by hash) having occurred three times in `history`.
+pub fn is_draw(state: &GameState, history: &[u64]) -> bool {
+    state.halfmove_clock >= 100 || history.iter().filter(|&&hash| hash == state.hash).count() >= 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::initialize_board;
+
+    #[test]
+    fn test_zobrist_hash_is_reproducible() {
+        let state = GameState::default();
+        assert_eq!(zobrist_hash(&state), zobrist_hash(&state));
+    }
+
+    #[test]
+    fn test_zobrist_hash_matches_incremental_field() {
+        let state = GameState::default();
+        assert_eq!(state.hash, zobrist_hash(&state));
+    }
+
+    #[test]
+    fn test_different_positions_hash_differently() {
+        let start = GameState::default();
+        let mut board = initialize_board();
+        board[6][4] = Cell::Empty;
+        board[4][4] = Cell::Piece(Color::White, PieceType::Pawn);
+        let after_e4 = GameState {
+            board,
+            ..GameState::default()
+        };
+        assert_ne!(zobrist_hash(&start), zobrist_hash(&after_e4));
+    }
+
+    #[test]
+    fn test_is_draw_detects_fifty_move_rule() {
+        let state = GameState {
+            halfmove_clock: 100,
+            ..GameState::default()
+        };
+        assert!(is_draw(&state, &[]));
+    }
+
+    #[test]
+    fn test_is_draw_detects_threefold_repetition() {
+        let state = GameState::default();
+        let history = vec![state.hash, state.hash, state.hash];
+        assert!(is_draw(&state, &history));
+    }
+}
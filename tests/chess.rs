@@ -1,10 +1,12 @@
-use rusty_chess::{initialize_board, make_move, parse_position};
+use rusty_chess::rules::{do_move, legal_moves};
+use rusty_chess::zobrist::is_draw;
+use rusty_chess::{initialize_board, parse_fen, parse_position, to_fen, Cell, Color, GameState, PieceType};
 
 #[test]
 fn test_initialize_board_integration() {
     let board = initialize_board();
-    assert_eq!(board[0][0], "R"); // Rook at a8
-    assert_eq!(board[1][0], "P"); // Pawn at a7
+    assert_eq!(board[0][0], Cell::Piece(Color::Black, PieceType::Rook)); // Rook at a8
+    assert_eq!(board[1][0], Cell::Piece(Color::Black, PieceType::Pawn)); // Pawn at a7
 }
 
 #[test]
@@ -13,8 +15,35 @@ fn test_parse_position_integration() {
 }
 
 #[test]
-fn test_make_move_integration() {
-    let mut board = initialize_board();
-    assert!(make_move(&mut board, "e2", "e4").is_ok());
-    assert_eq!(board[4][4], "P"); // Pawn at e4
+fn test_fen_import_export_integration() {
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    let state = parse_fen(fen).unwrap();
+    assert_eq!(state.board, initialize_board());
+    assert_eq!(to_fen(&state), fen);
+}
+
+#[test]
+fn test_legal_moves_from_starting_position_integration() {
+    let state = GameState::default();
+    assert_eq!(legal_moves(&state, Color::White).len(), 20);
+}
+
+#[test]
+fn test_repetition_draw_detection_integration() {
+    let mut state = GameState::default();
+    let mut history = vec![state.hash];
+
+    // Shuffle a knight back and forth three times to repeat the start position.
+    for _ in 0..3 {
+        do_move(&mut state, "g1", "f3");
+        history.push(state.hash);
+        do_move(&mut state, "g8", "f6");
+        history.push(state.hash);
+        do_move(&mut state, "f3", "g1");
+        history.push(state.hash);
+        do_move(&mut state, "f6", "g8");
+        history.push(state.hash);
+    }
+
+    assert!(is_draw(&state, &history));
 }